@@ -0,0 +1,297 @@
+use deno_ast::swc::ast as swc_ast;
+use deno_ast::swc::ast::{Expr, Lit, MemberProp, ModuleItem, Stmt};
+use deno_ast::{ParseParams, SourceTextInfo};
+use deno_core::ModuleSpecifier;
+
+/// Whether a rule file should be treated as CommonJS rather than an ES
+/// module: an explicit `.cjs` extension always means CommonJS; otherwise a
+/// sibling `package.json` declaring `"type": "module"` means ESM, and
+/// without one the file is CommonJS only if it has a top-level
+/// `module.exports`/`exports.<name>` assignment (or
+/// `Object.defineProperty(exports, ...)` call).
+///
+/// Detection is AST-based rather than a substring scan so that a genuine ES
+/// module which merely *mentions* `exports.` — in a string, a comment, or as
+/// a property of some other object (`foo.exports.bar`) — isn't mistakenly
+/// wrapped in the CJS shim, which would bury its real `export`/`import`
+/// statements inside the synthesized IIFE and break them.
+pub fn is_commonjs(specifier: &ModuleSpecifier, source: &str, package_json_is_esm: bool) -> bool {
+    if specifier.path().ends_with(".cjs") {
+        return true;
+    }
+
+    if package_json_is_esm {
+        return false;
+    }
+
+    has_cjs_export_statement(specifier, source)
+}
+
+/// Whether `source` has a top-level statement that actually assigns to
+/// `module.exports` or `exports.<name>`/`exports[...]`, or calls
+/// `Object.defineProperty(exports, ...)`. A source that fails to parse as a
+/// module is treated as not-CJS: by the time this runs the source has
+/// already gone through `transpile` if it needed to, so a parse failure
+/// here means something else is wrong, not that the file is CommonJS.
+fn has_cjs_export_statement(specifier: &ModuleSpecifier, source: &str) -> bool {
+    let Ok(parsed) = deno_ast::parse_module(ParseParams {
+        specifier: specifier.to_string(),
+        text_info: SourceTextInfo::from_string(source.to_string()),
+        media_type: deno_ast::MediaType::JavaScript,
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    }) else {
+        return false;
+    };
+
+    let Some(module) = parsed.program_ref().as_module() else {
+        return false;
+    };
+
+    module.body.iter().any(|item| {
+        let ModuleItem::Stmt(Stmt::Expr(expr_stmt)) = item else {
+            return false;
+        };
+
+        match expr_stmt.expr.as_ref() {
+            Expr::Assign(assign) => assign
+                .left
+                .as_simple()
+                .and_then(|t| t.as_member())
+                .is_some_and(|member| {
+                    is_module_exports(&member.obj, &member.prop) || is_exports(&member.obj)
+                }),
+            Expr::Call(call) => define_property_export_name(call).is_some(),
+            _ => false,
+        }
+    })
+}
+
+/// Parses `source` as a module and collects the names a CJS rule file
+/// exports via top-level `exports.<name> = ...` or
+/// `Object.defineProperty(exports, "<name>", ...)`. A `module.exports = ...`
+/// assignment adds no names, not even the keys of an object literal; its
+/// value is reachable only through the shim's default export.
+struct CjsExports {
+    named: Vec<String>,
+}
+
+fn collect_exports(specifier: &ModuleSpecifier, source: &str) -> Result<CjsExports, anyhow::Error> {
+    let parsed = deno_ast::parse_module(ParseParams {
+        specifier: specifier.to_string(),
+        text_info: SourceTextInfo::from_string(source.to_string()),
+        media_type: deno_ast::MediaType::JavaScript,
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    })?;
+
+    let module = parsed.program_ref().as_module();
+    let mut named = Vec::new();
+
+    if let Some(module) = module {
+        for item in &module.body {
+            let ModuleItem::Stmt(Stmt::Expr(expr_stmt)) = item else {
+                continue;
+            };
+
+            match expr_stmt.expr.as_ref() {
+                Expr::Assign(assign) => {
+                    if let Some(member) = assign.left.as_simple().and_then(|t| t.as_member()) {
+                        if is_exports(&member.obj) {
+                            if let Some(name) = member_name(&member.prop) {
+                                named.push(name);
+                            }
+                        }
+                    }
+                }
+                Expr::Call(call) => {
+                    if let Some(name) = define_property_export_name(call) {
+                        named.push(name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(CjsExports { named })
+}
+
+fn is_exports(obj: &Expr) -> bool {
+    is_ident(obj, "exports")
+}
+
+fn is_module_exports(obj: &Expr, prop: &MemberProp) -> bool {
+    is_ident(obj, "module") && member_name(prop).as_deref() == Some("exports")
+}
+
+fn is_ident(expr: &Expr, name: &str) -> bool {
+    matches!(expr, Expr::Ident(ident) if ident.sym.as_ref() == name)
+}
+
+fn member_name(prop: &MemberProp) -> Option<String> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(computed) => match computed.expr.as_ref() {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn define_property_export_name(call: &swc_ast::CallExpr) -> Option<String> {
+    let callee = call.callee.as_expr()?;
+    let member = callee.as_member()?;
+    if !is_ident(&member.obj, "Object") || member_name(&member.prop).as_deref() != Some("defineProperty") {
+        return None;
+    }
+
+    let target = call.args.first()?;
+    if !is_exports(&target.expr) {
+        return None;
+    }
+
+    let name_arg = call.args.get(1)?;
+    match name_arg.expr.as_ref() {
+        Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether `name` can be used verbatim as a binding identifier in
+/// `export const <name> = ...`. Reserved words aren't rejected here beyond
+/// `default` (handled separately): a rule file exporting e.g. `class` would
+/// be unusual enough that failing loudly via the resulting `SyntaxError` is
+/// fine, same as `deno`'s own CJS interop does.
+fn is_valid_export_binding(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    (first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Synthesizes an ES module that runs `source` inside a CJS-shaped closure
+/// (`module`, `exports`, `require` in scope) and re-exports whatever it
+/// assigned to `module.exports`/`exports.<name>`, so `load_side_module` can
+/// evaluate rule files written against the CommonJS convention.
+pub fn to_esm_shim(specifier: &ModuleSpecifier, source: String) -> Result<String, anyhow::Error> {
+    let exports = collect_exports(specifier, &source)?;
+
+    let mut wrapper = String::new();
+    wrapper.push_str(include_str!("require_shim.js"));
+    wrapper.push_str("\nconst module = { exports: {} };\nconst exports = module.exports;\n");
+    wrapper.push_str("(function (module, exports, require) {\n");
+    wrapper.push_str(&source);
+    wrapper.push_str("\n})(module, exports, require);\n");
+    wrapper.push_str("export default module.exports;\n");
+
+    // `default` is already covered by `export default module.exports` above,
+    // and a key can legitimately be assigned more than once (e.g. across
+    // branches of a conditional), so dedup before emitting. Names that aren't
+    // identifier-shaped (`"no-foo"`) are re-exported under their original
+    // string name via the `export { ... as "name" }` form instead of being
+    // silently dropped; reserved words pass `is_valid_export_binding` and are
+    // emitted as `export const` bindings.
+    let mut seen = std::collections::HashSet::new();
+    for name in &exports.named {
+        if name == "default" || !seen.insert(name) {
+            continue;
+        }
+
+        if is_valid_export_binding(name) {
+            wrapper.push_str(&format!(
+                "export const {name} = module.exports[{name:?}];\n"
+            ));
+        } else {
+            wrapper.push_str(&format!(
+                "const {mangled} = module.exports[{name:?}];\nexport {{ {mangled} as {name:?} }};\n",
+                mangled = format!("__escheck_export_{}", seen.len()),
+            ));
+        }
+    }
+
+    Ok(wrapper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifier() -> ModuleSpecifier {
+        ModuleSpecifier::parse("file:///rules/no-void.js").unwrap()
+    }
+
+    #[test]
+    fn is_commonjs_detects_cjs_extension_regardless_of_source() {
+        assert!(is_commonjs(
+            &ModuleSpecifier::parse("file:///rules/no-void.cjs").unwrap(),
+            "export default {};",
+            true
+        ));
+    }
+
+    #[test]
+    fn is_commonjs_defers_to_package_json_type_module() {
+        assert!(!is_commonjs(&specifier(), "exports.foo = 1;", true));
+    }
+
+    #[test]
+    fn is_commonjs_detects_exports_usage() {
+        assert!(is_commonjs(&specifier(), "module.exports = {};", false));
+        assert!(is_commonjs(&specifier(), "exports.create = () => {};", false));
+        assert!(!is_commonjs(&specifier(), "export default {};", false));
+    }
+
+    #[test]
+    fn is_commonjs_ignores_unrelated_mentions_of_exports() {
+        // A string/comment mention, or a property access on some other
+        // object, shouldn't be enough to trigger the CJS shim for a real ES
+        // module.
+        assert!(!is_commonjs(
+            &specifier(),
+            "// see exports. for details\nexport const msg = \"exports.foo\";",
+            false
+        ));
+        assert!(!is_commonjs(
+            &specifier(),
+            "export const x = foo.exports.bar;",
+            false
+        ));
+    }
+
+    #[test]
+    fn collect_exports_finds_named_and_defined_properties() {
+        let source = r#"
+            exports.create = function () {};
+            Object.defineProperty(exports, "meta", { value: {} });
+        "#;
+        let exports = collect_exports(&specifier(), source).unwrap();
+        assert_eq!(exports.named, vec!["create", "meta"]);
+    }
+
+    #[test]
+    fn to_esm_shim_dedups_repeated_assignments_to_the_same_key() {
+        let source = r#"
+            exports.create = function () {};
+            exports.create = function () {};
+        "#;
+        let shim = to_esm_shim(&specifier(), source.to_string()).unwrap();
+        assert_eq!(shim.matches("export const create").count(), 1);
+    }
+
+    #[test]
+    fn to_esm_shim_drops_default_and_remaps_non_identifier_keys() {
+        let source = r#"
+            exports.default = function () {};
+            exports["no-foo"] = 1;
+        "#;
+        let shim = to_esm_shim(&specifier(), source.to_string()).unwrap();
+        assert!(!shim.contains("export const default"));
+        assert!(shim.contains("as \"no-foo\""));
+    }
+}
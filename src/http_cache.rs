@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// On-disk cache for remote module sources, modeled after Deno's `http_cache`:
+/// each URL is hashed to a content-addressed path under `<cache_dir>/deps/<scheme>/<host>/<hash>`,
+/// with a `.metadata.json` sidecar carrying the response headers we care about.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    root: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CachedUrlMetadata {
+    pub headers: HashMap<String, String>,
+    pub url: String,
+}
+
+impl HttpCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Resolves the default cache root, mirroring `deno`'s `$HOME/.cache/escheck`.
+    pub fn default_root() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("escheck")
+    }
+
+    fn cache_filename(specifier: &url::Url) -> PathBuf {
+        let hash = {
+            let mut hasher = Sha256::new();
+            hasher.update(specifier.as_str().as_bytes());
+            format!("{:x}", hasher.finalize())
+        };
+
+        let mut path = PathBuf::from("deps");
+        path.push(specifier.scheme());
+        path.push(specifier.host_str().unwrap_or("-"));
+        path.push(hash);
+        path
+    }
+
+    fn content_path(&self, specifier: &url::Url) -> PathBuf {
+        self.root.join(Self::cache_filename(specifier))
+    }
+
+    fn metadata_path(&self, specifier: &url::Url) -> PathBuf {
+        let mut path = self.content_path(specifier);
+        path.set_extension("metadata.json");
+        path
+    }
+
+    pub async fn get(&self, specifier: &url::Url) -> Option<(Vec<u8>, CachedUrlMetadata)> {
+        let content = fs::read(self.content_path(specifier)).await.ok()?;
+        let metadata_raw = fs::read(self.metadata_path(specifier)).await.ok()?;
+        let metadata: CachedUrlMetadata = serde_json::from_slice(&metadata_raw).ok()?;
+        Some((content, metadata))
+    }
+
+    pub async fn set(
+        &self,
+        specifier: &url::Url,
+        headers: HashMap<String, String>,
+        content: &[u8],
+    ) -> Result<(), std::io::Error> {
+        let content_path = self.content_path(specifier);
+        if let Some(parent) = content_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        fs::write(&content_path, content).await?;
+
+        let metadata = CachedUrlMetadata {
+            headers,
+            url: specifier.to_string(),
+        };
+        let metadata_raw = serde_json::to_vec(&metadata)?;
+        fs::write(self.metadata_path(specifier), metadata_raw).await?;
+
+        Ok(())
+    }
+}
+
+pub fn header_map(response: &reqwest::Response) -> HashMap<String, String> {
+    response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_lowercase(), value.to_string()))
+        })
+        .collect()
+}
+
+impl CachedUrlMetadata {
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get("content-type").map(String::as_str)
+    }
+}
+
+pub fn is_remote(scheme: &str) -> bool {
+    matches!(scheme, "http" | "https")
+}
+
+/// Content-addressed cache for transpiled module output, keyed by a hash of
+/// the original source bytes so repeated loads of the same TS/JSX skip
+/// re-emitting identical JavaScript.
+#[derive(Debug, Clone)]
+pub struct EmitCache {
+    root: PathBuf,
+}
+
+impl EmitCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn emit_path(&self, source_hash: &str) -> PathBuf {
+        self.root.join("emit").join(format!("{source_hash}.js"))
+    }
+
+    pub async fn get(&self, source_hash: &str) -> Option<String> {
+        let bytes = fs::read(self.emit_path(source_hash)).await.ok()?;
+        String::from_utf8(bytes).ok()
+    }
+
+    pub async fn set(&self, source_hash: &str, emitted: &str) -> Result<(), std::io::Error> {
+        let path = self.emit_path(source_hash);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(path, emitted).await
+    }
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_path_is_scoped_by_scheme_and_host() {
+        let cache = HttpCache::new(PathBuf::from("/tmp/escheck-test-cache"));
+        let specifier = url::Url::parse("https://unpkg.com/eslint-plugin-foo/rules/bar.js").unwrap();
+        let path = cache.content_path(&specifier);
+        assert!(path.starts_with("/tmp/escheck-test-cache/deps/https/unpkg.com"));
+    }
+
+    #[test]
+    fn content_path_is_deterministic() {
+        let cache = HttpCache::new(PathBuf::from("/tmp/escheck-test-cache"));
+        let specifier = url::Url::parse("https://unpkg.com/a.js").unwrap();
+        assert_eq!(cache.content_path(&specifier), cache.content_path(&specifier));
+    }
+
+    #[test]
+    fn different_urls_hash_to_different_paths() {
+        let cache = HttpCache::new(PathBuf::from("/tmp/escheck-test-cache"));
+        let a = url::Url::parse("https://unpkg.com/a.js").unwrap();
+        let b = url::Url::parse("https://unpkg.com/b.js").unwrap();
+        assert_ne!(cache.content_path(&a), cache.content_path(&b));
+    }
+
+    #[test]
+    fn metadata_path_sits_next_to_content_path_but_differs() {
+        let cache = HttpCache::new(PathBuf::from("/tmp/escheck-test-cache"));
+        let specifier = url::Url::parse("https://unpkg.com/a.js").unwrap();
+        let content_path = cache.content_path(&specifier);
+        let metadata_path = cache.metadata_path(&specifier);
+        assert_ne!(content_path, metadata_path);
+        assert_eq!(metadata_path.parent(), content_path.parent());
+    }
+}
@@ -0,0 +1,321 @@
+use crate::rule::RuleId;
+use deno_ast::swc::ast::{
+    ArrowExpr, AssignExpr, BinExpr, CallExpr, CondExpr, FnDecl, Ident, IfStmt, MemberExpr,
+    NewExpr, ObjectLit, ReturnStmt, SwitchStmt, ThrowStmt, TryStmt, VarDecl, WhileStmt,
+};
+use deno_ast::swc::common::Span;
+use deno_ast::swc::visit::{Visit, VisitWith};
+use deno_core::v8;
+use deno_core::JsRuntime;
+use serde::{Deserialize, Serialize};
+
+/// A byte range into the linted file, matching the `start`/`end` pair ESLint
+/// rules expect on the nodes passed to their visitor callbacks.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SourceLoc {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Default for Severity {
+    fn default() -> Self {
+        Severity::Error
+    }
+}
+
+/// A single text edit a rule's `context.report({ fix })` asked for, in the
+/// same `{range, text}` shape ESLint's `fixer` methods return.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fix {
+    pub range: (usize, usize),
+    pub text: String,
+}
+
+/// One finding from a rule's `context.report(...)` call, as pushed by
+/// `op_escheck_report`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub rule_id: RuleId,
+    pub loc: SourceLoc,
+    pub message: String,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub fix: Option<Fix>,
+}
+
+/// The shape `op_escheck_report` accepts from JS. `rule_id` isn't part of
+/// it: the op stamps the currently-executing rule onto every diagnostic
+/// itself, since the visitor callback can't be trusted to know its own id.
+#[derive(Debug, Deserialize)]
+pub struct ReportDescriptor {
+    pub loc: SourceLoc,
+    pub message: String,
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    #[serde(default)]
+    pub fix: Option<Fix>,
+}
+
+/// Calls the rule's `create(context)` and returns the visitor object it
+/// hands back (a plain object mapping AST node types, e.g.
+/// `"VariableDeclaration"`, to callback functions).
+pub fn run_create(
+    runtime: &mut JsRuntime,
+    rule_namespace: &v8::Global<v8::Value>,
+    source: &str,
+) -> Result<v8::Global<v8::Value>, anyhow::Error> {
+    let wrapper_src = format!(
+        "(function (create) {{
+  const fixer = {{
+    replaceText(node, text) {{ return {{ range: [node.start, node.end], text }}; }},
+    replaceTextRange(range, text) {{ return {{ range, text }}; }},
+    insertTextBefore(node, text) {{ return {{ range: [node.start, node.start], text }}; }},
+    insertTextAfter(node, text) {{ return {{ range: [node.end, node.end], text }}; }},
+    remove(node) {{ return {{ range: [node.start, node.end], text: '' }}; }},
+  }};
+  const context = {{
+    report(descriptor) {{
+      const node = descriptor.node || {{}};
+      let fix;
+      if (typeof descriptor.fix === 'function') {{
+        fix = descriptor.fix(fixer);
+      }} else if (descriptor.fix) {{
+        fix = descriptor.fix;
+      }}
+      Deno.core.ops.op_escheck_report({{
+        loc: {{ start: node.start || 0, end: node.end || 0 }},
+        message: descriptor.message || descriptor.messageId || '',
+        severity: descriptor.severity,
+        fix: fix ? {{ range: [fix.range[0], fix.range[1]], text: fix.text }} : undefined,
+      }});
+    }},
+    getSourceCode() {{
+      return {{
+        text: {source},
+        getText() {{ return this.text; }},
+      }};
+    }},
+  }};
+  return create(context);
+}})",
+        source = serde_json::to_string(source)?
+    );
+
+    let scope = &mut runtime.handle_scope();
+
+    let namespace = v8::Local::new(scope, rule_namespace);
+    let namespace_obj: v8::Local<v8::Object> = namespace
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("rule module namespace is not an object"))?;
+
+    let default_key = v8::String::new(scope, "default").unwrap();
+    let default_export = namespace_obj
+        .get(scope, default_key.into())
+        .ok_or_else(|| anyhow::anyhow!("rule module has no default export"))?;
+    let default_obj: v8::Local<v8::Object> = default_export
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("rule module's default export is not an object"))?;
+
+    let create_key = v8::String::new(scope, "create").unwrap();
+    let create_fn = default_obj
+        .get(scope, create_key.into())
+        .ok_or_else(|| anyhow::anyhow!("rule does not export a `create` function"))?;
+
+    let wrapper_text = v8::String::new(scope, &wrapper_src).unwrap();
+    let wrapper_script = v8::Script::compile(scope, wrapper_text, None)
+        .ok_or_else(|| anyhow::anyhow!("failed to compile the lint context wrapper"))?;
+    let wrapper_fn: v8::Local<v8::Function> = wrapper_script
+        .run(scope)
+        .ok_or_else(|| anyhow::anyhow!("failed to instantiate the lint context wrapper"))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("lint context wrapper did not evaluate to a function"))?;
+
+    let undefined = v8::undefined(scope).into();
+    let visitor = wrapper_fn
+        .call(scope, undefined, &[create_fn])
+        .ok_or_else(|| anyhow::anyhow!("rule's `create(context)` threw"))?;
+
+    Ok(v8::Global::new(scope, visitor))
+}
+
+/// Walks every node of the parsed module via `swc`'s generic `Visit`, the
+/// way ESLint's `Linter` traverses the full ESTree rather than just the
+/// top-level statement list, dispatching each recognized node kind to the
+/// matching visitor callback.
+///
+/// `deno_ast` spans are `BytePos`es offset from the parse's source-map base
+/// (`module.span.lo`, not `0`), so that base is subtracted back out before a
+/// position is handed to JS as `node.start`/`node.end`.
+pub fn walk(
+    runtime: &mut JsRuntime,
+    visitor: &v8::Global<v8::Value>,
+    module: &deno_ast::swc::ast::Module,
+) -> Result<(), anyhow::Error> {
+    let mut dispatcher = DispatchVisitor {
+        runtime,
+        visitor,
+        base: module.span.lo.0,
+        error: None,
+    };
+    module.visit_with(&mut dispatcher);
+    match dispatcher.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+struct DispatchVisitor<'a> {
+    runtime: &'a mut JsRuntime,
+    visitor: &'a v8::Global<v8::Value>,
+    base: u32,
+    error: Option<anyhow::Error>,
+}
+
+impl<'a> DispatchVisitor<'a> {
+    fn dispatch(&mut self, node_type: &str, span: Span) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(err) = dispatch(self.runtime, self.visitor, node_type, span, self.base) {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl<'a> Visit for DispatchVisitor<'a> {
+    fn visit_var_decl(&mut self, node: &VarDecl) {
+        self.dispatch("VariableDeclaration", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        self.dispatch("CallExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_new_expr(&mut self, node: &NewExpr) {
+        self.dispatch("NewExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, node: &Ident) {
+        self.dispatch("Identifier", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, node: &MemberExpr) {
+        self.dispatch("MemberExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_if_stmt(&mut self, node: &IfStmt) {
+        self.dispatch("IfStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_while_stmt(&mut self, node: &WhileStmt) {
+        self.dispatch("WhileStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_return_stmt(&mut self, node: &ReturnStmt) {
+        self.dispatch("ReturnStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_throw_stmt(&mut self, node: &ThrowStmt) {
+        self.dispatch("ThrowStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_try_stmt(&mut self, node: &TryStmt) {
+        self.dispatch("TryStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_switch_stmt(&mut self, node: &SwitchStmt) {
+        self.dispatch("SwitchStatement", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.dispatch("FunctionDeclaration", node.function.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        self.dispatch("ArrowFunctionExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_bin_expr(&mut self, node: &BinExpr) {
+        self.dispatch("BinaryExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_cond_expr(&mut self, node: &CondExpr) {
+        self.dispatch("ConditionalExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, node: &AssignExpr) {
+        self.dispatch("AssignmentExpression", node.span);
+        node.visit_children_with(self);
+    }
+
+    fn visit_object_lit(&mut self, node: &ObjectLit) {
+        self.dispatch("ObjectExpression", node.span);
+        node.visit_children_with(self);
+    }
+}
+
+fn dispatch(
+    runtime: &mut JsRuntime,
+    visitor: &v8::Global<v8::Value>,
+    node_type: &str,
+    span: Span,
+    base: u32,
+) -> Result<(), anyhow::Error> {
+    let scope = &mut runtime.handle_scope();
+
+    let local_visitor = v8::Local::new(scope, visitor);
+    let visitor_obj: v8::Local<v8::Object> = local_visitor
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("rule's `create()` did not return an object"))?;
+
+    let key = v8::String::new(scope, node_type).unwrap();
+    let Some(callback) = visitor_obj.get(scope, key.into()) else {
+        return Ok(());
+    };
+    if !callback.is_function() {
+        return Ok(());
+    }
+    let callback_fn: v8::Local<v8::Function> = callback.try_into().unwrap();
+
+    let node = v8::Object::new(scope);
+
+    let type_key = v8::String::new(scope, "type").unwrap();
+    let type_val = v8::String::new(scope, node_type).unwrap();
+    node.set(scope, type_key.into(), type_val.into());
+
+    let start_key = v8::String::new(scope, "start").unwrap();
+    let start_val = v8::Number::new(scope, span.lo.0.saturating_sub(base) as f64);
+    node.set(scope, start_key.into(), start_val.into());
+
+    let end_key = v8::String::new(scope, "end").unwrap();
+    let end_val = v8::Number::new(scope, span.hi.0.saturating_sub(base) as f64);
+    node.set(scope, end_key.into(), end_val.into());
+
+    let undefined = v8::undefined(scope).into();
+    callback_fn.call(scope, undefined, &[node.into()]);
+
+    Ok(())
+}
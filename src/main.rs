@@ -1,4 +1,9 @@
+mod cjs;
+mod http_cache;
+mod lint;
+mod media_type;
 mod rule;
+mod rule_cache;
 mod rule_exec_env_ffi;
 mod rule_executor;
 
@@ -9,11 +14,12 @@ use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
-    let mut re = RuleExecutor::new()?;
-
     let args: Vec<String> = std::env::args().collect();
+    let reload = args.iter().any(|arg| arg == "--reload");
+
+    let mut re = RuleExecutor::new_with_reload(reload)?;
 
-    for arg in args.iter().skip(1) {
+    for arg in args.iter().skip(1).filter(|arg| *arg != "--reload") {
         let file: PathBuf = arg.into();
         let _ = re.load_file(&file).await;
     }
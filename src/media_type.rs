@@ -0,0 +1,150 @@
+use deno_core::ModuleSpecifier;
+
+/// The source language of a loaded module, detected from the `Content-Type`
+/// header of a remote response when it is recognized and, failing that (or
+/// for local files), from the specifier's extension. Only the media types
+/// escheck's loader needs to tell apart.
+///
+/// Unlike `deno`, this is never refined by an `import ... assert { type:
+/// "json" }` clause: the `deno_core` version this loader is built against
+/// doesn't pass assertions to `ModuleLoader::load`, so a `.json` specifier
+/// is always selected by extension/content-type, and a mismatched or
+/// missing assertion can't be rejected here.
+///
+/// TODO(escheck): this is a known gap, not a design choice — an
+/// assertion-typed JSON import of a non-`.json` URL silently loads as
+/// JavaScript instead of erroring, and an unsupported assertion type is
+/// never rejected "as `deno` does." Closing it needs a `deno_core` bump
+/// that exposes assertions on `ModuleLoader::load`; flag this to whoever
+/// owns that upgrade rather than treating import-assertion support as
+/// delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Jsx,
+    Json,
+    Dts,
+}
+
+impl MediaType {
+    /// Whether this media type needs to go through `deno_ast`'s transpiler
+    /// before V8 can evaluate it.
+    pub fn requires_transpilation(&self) -> bool {
+        matches!(self, MediaType::TypeScript | MediaType::Tsx | MediaType::Jsx)
+    }
+
+    pub fn from_specifier(specifier: &ModuleSpecifier) -> Self {
+        let path = specifier.path();
+        if path.ends_with(".d.ts") {
+            return MediaType::Dts;
+        }
+        match path.rsplit('.').next() {
+            Some("ts") => MediaType::TypeScript,
+            Some("tsx") => MediaType::Tsx,
+            Some("jsx") => MediaType::Jsx,
+            Some("json") => MediaType::Json,
+            _ => MediaType::JavaScript,
+        }
+    }
+
+    /// Refines a specifier-derived guess using a `Content-Type` header, the
+    /// way `deno`'s module loader lets the server override extensionless
+    /// URLs.
+    pub fn from_content_type(specifier: &ModuleSpecifier, content_type: Option<&str>) -> Self {
+        let from_header = content_type.and_then(|content_type| {
+            let mime = content_type.split(';').next().unwrap_or("").trim();
+            match mime {
+                "application/typescript" | "text/typescript" | "video/vnd.dlna.mpeg-tts" => {
+                    Some(MediaType::TypeScript)
+                }
+                "text/tsx" => Some(MediaType::Tsx),
+                "text/jsx" => Some(MediaType::Jsx),
+                "application/json" => Some(MediaType::Json),
+                "application/javascript"
+                | "text/javascript"
+                | "application/ecmascript"
+                | "text/ecmascript" => Some(MediaType::JavaScript),
+                _ => None,
+            }
+        });
+
+        from_header.unwrap_or_else(|| Self::from_specifier(specifier))
+    }
+
+    pub fn as_deno_ast_media_type(&self) -> deno_ast::MediaType {
+        match self {
+            MediaType::JavaScript => deno_ast::MediaType::JavaScript,
+            MediaType::TypeScript => deno_ast::MediaType::TypeScript,
+            MediaType::Tsx => deno_ast::MediaType::Tsx,
+            MediaType::Jsx => deno_ast::MediaType::Jsx,
+            MediaType::Json => deno_ast::MediaType::Json,
+            MediaType::Dts => deno_ast::MediaType::Dts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifier(url: &str) -> ModuleSpecifier {
+        ModuleSpecifier::parse(url).unwrap()
+    }
+
+    #[test]
+    fn from_specifier_detects_extensions() {
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/no-void.ts")),
+            MediaType::TypeScript
+        );
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/no-void.tsx")),
+            MediaType::Tsx
+        );
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/no-void.jsx")),
+            MediaType::Jsx
+        );
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/config.json")),
+            MediaType::Json
+        );
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/no-void.js")),
+            MediaType::JavaScript
+        );
+    }
+
+    #[test]
+    fn from_specifier_detects_dts_before_extension() {
+        assert_eq!(
+            MediaType::from_specifier(&specifier("file:///rules/index.d.ts")),
+            MediaType::Dts
+        );
+    }
+
+    #[test]
+    fn from_content_type_overrides_extensionless_specifier() {
+        let url = specifier("https://unpkg.com/eslint-plugin-foo/rules/bar");
+        assert_eq!(
+            MediaType::from_content_type(&url, Some("application/typescript; charset=utf-8")),
+            MediaType::TypeScript
+        );
+        assert_eq!(
+            MediaType::from_content_type(&url, Some("application/json")),
+            MediaType::Json
+        );
+    }
+
+    #[test]
+    fn from_content_type_falls_back_to_specifier_when_unrecognized() {
+        let url = specifier("https://unpkg.com/eslint-plugin-foo/rules/bar.ts");
+        assert_eq!(
+            MediaType::from_content_type(&url, Some("text/html")),
+            MediaType::TypeScript
+        );
+        assert_eq!(MediaType::from_content_type(&url, None), MediaType::TypeScript);
+    }
+}
@@ -17,7 +17,7 @@ impl From<derive_builder::UninitializedFieldError> for RuleError<'_> {
     }
 }
 
-#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct RuleId(u128);
 
 impl RuleId {
@@ -0,0 +1,111 @@
+use crate::rule::Rule;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// A fast, non-cryptographic rolling hash (FNV-1a) used only to fingerprint
+/// rule source bytes for cache lookups. It is not collision-resistant: two
+/// inputs with the same 64-bit checksum share one cache entry, so a
+/// collision serves the other input's cached `Rule`.
+pub struct FastInsecureHasher(u64);
+
+impl FastInsecureHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) -> &mut Self {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+        self
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for FastInsecureHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Persistent cache of already-compiled rule metadata, keyed by a checksum
+/// of the rule file's bytes plus the `JS_SNAPSHOT` build, so a snapshot
+/// rebuild (which can change prelude semantics) never serves a stale entry.
+/// Storing just `Rule` (`name` + `Meta`) sidesteps the cost of spinning up
+/// module evaluation for rule files `load_file` has already seen.
+#[derive(Debug, Clone)]
+pub struct RuleCache {
+    root: PathBuf,
+}
+
+impl RuleCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn checksum(source: &[u8], js_snapshot: &[u8]) -> u64 {
+        FastInsecureHasher::new().write(source).write(js_snapshot).finish()
+    }
+
+    fn entry_path(&self, checksum: u64) -> PathBuf {
+        self.root
+            .join("rule-meta")
+            .join(format!("{checksum:016x}.json"))
+    }
+
+    pub async fn get(&self, checksum: u64) -> Option<Rule> {
+        let raw = fs::read(self.entry_path(checksum)).await.ok()?;
+        serde_json::from_slice(&raw).ok()
+    }
+
+    pub async fn set(&self, checksum: u64, rule: &Rule) -> Result<(), std::io::Error> {
+        let path = self.entry_path(checksum);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let raw = serde_json::to_vec(rule)?;
+        fs::write(path, raw).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_is_deterministic() {
+        let checksum = RuleCache::checksum(b"exports.create = () => {};", b"snapshot-v1");
+        assert_eq!(checksum, RuleCache::checksum(b"exports.create = () => {};", b"snapshot-v1"));
+    }
+
+    #[test]
+    fn checksum_changes_with_source() {
+        let a = RuleCache::checksum(b"exports.create = () => {};", b"snapshot-v1");
+        let b = RuleCache::checksum(b"exports.create = () => 1;", b"snapshot-v1");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn checksum_changes_with_snapshot() {
+        let a = RuleCache::checksum(b"exports.create = () => {};", b"snapshot-v1");
+        let b = RuleCache::checksum(b"exports.create = () => {};", b"snapshot-v2");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn entry_path_is_scoped_by_root_and_checksum() {
+        let cache = RuleCache::new(PathBuf::from("/tmp/escheck-test-cache"));
+        let path = cache.entry_path(0xdead_beef);
+        assert_eq!(
+            path,
+            PathBuf::from("/tmp/escheck-test-cache/rule-meta/00000000deadbeef.json")
+        );
+    }
+}
@@ -1,18 +1,83 @@
+use crate::lint::{Diagnostic, ReportDescriptor, Severity};
 use crate::rule::{Rule, RuleId};
+use anyhow::bail;
 use dashmap::DashMap;
 use deno_core::error::AnyError;
 use deno_core::*;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[derive(Clone, Debug)]
 pub struct InnerState {
     pub id: uuid::Uuid,
     pub rule_map: Arc<DashMap<RuleId, Rule>>,
+
+    /// Set by `op_escheck_rule_new` to the id it just assigned, so
+    /// `RuleExecutor::load` can associate that id with the module namespace
+    /// it just evaluated, without JS needing to know its own id.
+    pub last_registered_rule: Arc<Mutex<Option<RuleId>>>,
+
+    /// The rule currently executing a visitor callback, read by
+    /// `op_escheck_report` to stamp diagnostics without trusting JS to
+    /// supply its own id.
+    pub current_rule: Arc<Mutex<Option<RuleId>>>,
+
+    pub diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
 }
 
 #[op]
 pub fn op_escheck_rule_new(state: &mut OpState, rule: Rule) -> Result<(), AnyError> {
     let inner_state = state.try_borrow_mut::<InnerState>().unwrap();
-    inner_state.rule_map.insert(RuleId::next(), rule);
+    let id = RuleId::next();
+    inner_state.rule_map.insert(id.clone(), rule);
+    *inner_state.last_registered_rule.lock().unwrap() = Some(id);
     Ok(())
 }
+
+/// Backs `context.report(...)`. The currently-linting rule is stamped onto
+/// the diagnostic from `InnerState::current_rule`, which `RuleExecutor`
+/// sets just before invoking a visitor callback.
+#[op]
+pub fn op_escheck_report(state: &mut OpState, report: ReportDescriptor) -> Result<(), AnyError> {
+    let inner_state = state.try_borrow_mut::<InnerState>().unwrap();
+
+    let rule_id = inner_state
+        .current_rule
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("context.report() called outside of a lint pass"))?;
+
+    inner_state.diagnostics.lock().unwrap().push(Diagnostic {
+        rule_id,
+        loc: report.loc,
+        message: report.message,
+        severity: report.severity.unwrap_or(Severity::Error),
+        fix: report.fix,
+    });
+
+    Ok(())
+}
+
+/// Backs the `require()` shim synthesized for CommonJS rule files: resolves
+/// `specifier` against `referrer` and reads the target back synchronously,
+/// since CJS `require` cannot yield to the event loop the way `import` can.
+/// Only `file://` specifiers are supported today; remote `require()`s should
+/// go through a prior `import` so the module ends up in the on-disk cache.
+#[op]
+pub fn op_escheck_require_resolve(specifier: String, referrer: String) -> Result<String, AnyError> {
+    let resolved = deno_core::resolve_import(&specifier, &referrer)?;
+
+    if resolved.scheme() != "file" {
+        bail!(
+            "require(\"{}\") cannot resolve non-file specifier `{}` synchronously",
+            specifier,
+            resolved
+        );
+    }
+
+    let path = resolved
+        .to_file_path()
+        .map_err(|_| anyhow::anyhow!("Invalid file URL `{}`", resolved))?;
+
+    Ok(std::fs::read_to_string(path)?)
+}
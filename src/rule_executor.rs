@@ -1,3 +1,5 @@
+use crate::http_cache::{content_hash, header_map, is_remote, EmitCache, HttpCache};
+use crate::media_type::MediaType;
 use crate::rule::*;
 use crate::rule_exec_env_ffi::*;
 use anyhow::bail;
@@ -9,14 +11,92 @@ use deno_core::ModuleSource;
 use deno_core::ModuleSourceFuture;
 use deno_core::ModuleSpecifier;
 use deno_core::ModuleType;
+use deno_core::v8;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use thiserror::*;
 use tokio::fs;
 
-pub struct NetModuleLoader;
+pub struct NetModuleLoader {
+    http_client: reqwest::Client,
+    http_cache: HttpCache,
+    emit_cache: EmitCache,
+    reload: bool,
+}
+
+impl NetModuleLoader {
+    pub fn new(reload: bool) -> Self {
+        let cache_root = HttpCache::default_root();
+        Self {
+            http_client: reqwest::Client::new(),
+            http_cache: HttpCache::new(cache_root.clone()),
+            emit_cache: EmitCache::new(cache_root),
+            reload,
+        }
+    }
+}
+
+async fn fetch_remote(
+    http_client: &reqwest::Client,
+    http_cache: &HttpCache,
+    reload: bool,
+    specifier: &ModuleSpecifier,
+) -> Result<(Vec<u8>, Option<String>, ModuleSpecifier), anyhow::Error> {
+    if !reload {
+        if let Some((content, metadata)) = http_cache.get(specifier).await {
+            let content_type = metadata.content_type().map(str::to_string);
+            return Ok((content, content_type, specifier.clone()));
+        }
+    }
+
+    // `reqwest` follows redirects by default, landing us on the final
+    // specifier's response (e.g. unpkg's `/pkg` -> `/pkg@version/...`), so
+    // `response.url()` — not the pre-redirect `specifier` — is what every
+    // relative import inside the fetched module must resolve against, and
+    // what we cache the content under.
+    let response = http_client.get(specifier.clone()).send().await?;
+    let response = response.error_for_status()?;
+    let final_specifier = response.url().clone();
+    let headers = header_map(&response);
+    let content_type = headers.get("content-type").cloned();
+    let bytes = response.bytes().await?.to_vec();
+
+    http_cache.set(&final_specifier, headers, &bytes).await?;
+
+    Ok((bytes, content_type, final_specifier))
+}
+
+/// Transpiles TypeScript/JSX/TSX source to plain JavaScript with `deno_ast`,
+/// caching the emitted output by a hash of the input so repeated loads of
+/// the same source skip re-parsing and re-emitting.
+async fn transpile(
+    emit_cache: &EmitCache,
+    specifier: &ModuleSpecifier,
+    media_type: MediaType,
+    source: String,
+) -> Result<String, anyhow::Error> {
+    let hash = content_hash(source.as_bytes());
+    if let Some(cached) = emit_cache.get(&hash).await {
+        return Ok(cached);
+    }
+
+    let parsed = deno_ast::parse_module(deno_ast::ParseParams {
+        specifier: specifier.to_string(),
+        text_info: deno_ast::SourceTextInfo::from_string(source),
+        media_type: media_type.as_deno_ast_media_type(),
+        capture_tokens: false,
+        scope_analysis: false,
+        maybe_syntax: None,
+    })?;
+
+    let emitted = parsed.transpile(&Default::default())?;
+
+    emit_cache.set(&hash, &emitted.text).await?;
+
+    Ok(emitted.text)
+}
 
 impl ModuleLoader for NetModuleLoader {
     fn resolve(
@@ -35,34 +115,66 @@ impl ModuleLoader for NetModuleLoader {
         _is_dyn_import: bool,
     ) -> Pin<Box<ModuleSourceFuture>> {
         let module_specifier = module_specifier.clone();
+        let http_client = self.http_client.clone();
+        let http_cache = self.http_cache.clone();
+        let emit_cache = self.emit_cache.clone();
+        let reload = self.reload;
         async move {
             let scheme = module_specifier.scheme().to_string();
             let string_specifier = module_specifier.to_string();
+            let mut found_specifier = module_specifier.clone();
 
-            let bytes: Vec<u8> = match scheme.clone().as_str() {
+            let (bytes, media_type) = match scheme.as_str() {
                 "file" => {
                     let path = match module_specifier.to_file_path() {
                         Ok(path) => path,
                         Err(_) => bail!("Invalid file URL."),
                     };
-                    fs::read(path).await?
+                    let bytes = fs::read(path).await?;
+                    let media_type = MediaType::from_specifier(&module_specifier);
+                    (bytes, media_type)
+                }
+                scheme if is_remote(scheme) => {
+                    let (bytes, content_type, final_specifier) =
+                        fetch_remote(&http_client, &http_cache, reload, &module_specifier).await?;
+                    let media_type =
+                        MediaType::from_content_type(&final_specifier, content_type.as_deref());
+                    found_specifier = final_specifier;
+                    (bytes, media_type)
                 }
                 schema => bail!("Invalid schema {}", schema),
             };
 
             // Strip BOM
-            let code = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            let bytes = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
                 bytes.as_slice()[3..].to_vec()
             } else {
                 bytes
-            }
-            .into_boxed_slice();
+            };
+
+            // `_maybe_referrer` aside, this `deno_core` version gives
+            // `ModuleLoader::load` no way to see an `import ... assert {
+            // type: "json" }` clause at all, so `media_type` is decided
+            // purely from `found_specifier`/content-type below. See the
+            // TODO on `MediaType`'s doc comment: this is an open gap, not a
+            // design decision — until `deno_core` is bumped, a mismatched
+            // or missing assertion can never be rejected here.
+            let (code, module_type) = if media_type.requires_transpilation() {
+                let source = String::from_utf8(bytes)?;
+                let emitted =
+                    transpile(&emit_cache, &found_specifier, media_type, source).await?;
+                (emitted.into_bytes().into_boxed_slice(), ModuleType::JavaScript)
+            } else if media_type == MediaType::Json {
+                (bytes.into_boxed_slice(), ModuleType::Json)
+            } else {
+                (bytes.into_boxed_slice(), ModuleType::JavaScript)
+            };
 
             let module = ModuleSource {
                 code,
-                module_type: ModuleType::JavaScript,
-                module_url_specified: string_specifier.clone(),
-                module_url_found: string_specifier.to_string(),
+                module_type,
+                module_url_specified: string_specifier,
+                module_url_found: found_specifier.to_string(),
             };
 
             Ok(module)
@@ -71,6 +183,29 @@ impl ModuleLoader for NetModuleLoader {
     }
 }
 
+/// Whether the `package.json` next to a `file://` specifier declares
+/// `"type": "module"`. Remote specifiers and files with no sibling
+/// `package.json` get `false`, so `is_commonjs` then decides from the
+/// file's extension and source alone.
+async fn package_json_declares_esm(specifier: &ModuleSpecifier) -> bool {
+    let Ok(path) = specifier.to_file_path() else {
+        return false;
+    };
+    let Some(dir) = path.parent() else {
+        return false;
+    };
+
+    let Ok(contents) = fs::read_to_string(dir.join("package.json")).await else {
+        return false;
+    };
+
+    serde_json::from_str::<serde_json::Value>(&contents)
+        .ok()
+        .and_then(|value| value.get("type").and_then(|t| t.as_str().map(str::to_string)))
+        .as_deref()
+        == Some("module")
+}
+
 static JS_SNAPSHOT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/JS_SNAPSHOT.bin"));
 
 #[derive(Error, Debug)]
@@ -98,26 +233,84 @@ pub enum RuleExecutorError {
 
     #[error("Could not read file {file:?} due to {err:?}")]
     CouldNotReadFile { file: PathBuf, err: std::io::Error },
+
+    #[error("The module `{module_name}` could not be shimmed from CommonJS to ESM: {reason:?}")]
+    CjsShimError {
+        module_name: String,
+        reason: anyhow::Error,
+    },
+
+    #[error("No rule registered under id {rule_id:?}")]
+    UnknownRule { rule_id: RuleId },
+
+    #[error("Could not parse {file:?} as {media_type:?}: {reason:?}")]
+    ParseError {
+        file: PathBuf,
+        media_type: crate::media_type::MediaType,
+        reason: anyhow::Error,
+    },
 }
 
 pub struct RuleExecutor {
     runtime: deno_core::JsRuntime,
     pub rule_map: Arc<DashMap<RuleId, Rule>>,
+
+    /// Module namespaces for every rule that's registered itself via
+    /// `op_escheck_rule_new`, keyed by the id `load` associated with it.
+    /// `lint_file` pulls `default.create` back out of these to run a rule.
+    rule_namespaces: DashMap<RuleId, v8::Global<v8::Value>>,
+    last_registered_rule: Arc<Mutex<Option<RuleId>>>,
+    current_rule: Arc<Mutex<Option<RuleId>>>,
+    diagnostics: Arc<Mutex<Vec<crate::lint::Diagnostic>>>,
+    rule_cache: crate::rule_cache::RuleCache,
+
+    /// The file each rule id was last loaded from, so `lint_file` can
+    /// re-evaluate a rule that was served from `rule_cache` (and therefore
+    /// never got a `rule_namespaces` entry) on demand instead of failing.
+    rule_sources: DashMap<RuleId, PathBuf>,
+
+    /// Mirrors [`NetModuleLoader`]'s own flag: when set, `load_file` skips
+    /// `rule_cache` reads too, so `--reload` busts the rule-metadata cache
+    /// for local files the same way it busts the HTTP cache for remote ones.
+    reload: bool,
+
+    /// Shares its on-disk cache root with the [`NetModuleLoader`]'s own
+    /// `EmitCache`, so `load_file` can transpile a local `.ts`/`.tsx`/`.jsx`
+    /// rule file itself before handing source to `load_side_module`, which
+    /// (unlike an `import`) never goes through `NetModuleLoader::load`.
+    emit_cache: EmitCache,
 }
 
 impl RuleExecutor {
     pub fn new() -> Result<RuleExecutor, RuleExecutorError> {
+        Self::new_with_reload(false)
+    }
+
+    /// Like [`RuleExecutor::new`], but when `reload` is `true` the
+    /// [`NetModuleLoader`] bypasses its on-disk HTTP cache and re-fetches
+    /// every remote module, mirroring `deno run --reload`.
+    pub fn new_with_reload(reload: bool) -> Result<RuleExecutor, RuleExecutorError> {
         let rule_map = Arc::new(DashMap::new());
+        let last_registered_rule = Arc::new(Mutex::new(None));
+        let current_rule = Arc::new(Mutex::new(None));
+        let diagnostics = Arc::new(Mutex::new(Vec::new()));
 
         let extension: deno_core::Extension = {
             let rule_map = rule_map.clone();
             let inner_state = InnerState {
                 id: uuid::Uuid::new_v4(),
                 rule_map,
+                last_registered_rule: last_registered_rule.clone(),
+                current_rule: current_rule.clone(),
+                diagnostics: diagnostics.clone(),
             };
 
             Extension::builder()
-                .ops(vec![crate::rule_exec_env_ffi::op_escheck_rule_new::decl()])
+                .ops(vec![
+                    crate::rule_exec_env_ffi::op_escheck_rule_new::decl(),
+                    crate::rule_exec_env_ffi::op_escheck_require_resolve::decl(),
+                    crate::rule_exec_env_ffi::op_escheck_report::decl(),
+                ])
                 .state(move |state| {
                     state.put(inner_state.clone());
                     Ok(())
@@ -127,13 +320,25 @@ impl RuleExecutor {
 
         let rt_options = deno_core::RuntimeOptions {
             startup_snapshot: Some(deno_core::Snapshot::Static(JS_SNAPSHOT)),
-            module_loader: Some(Rc::new(NetModuleLoader)),
+            module_loader: Some(Rc::new(NetModuleLoader::new(reload))),
             extensions: vec![extension, deno_console::init()],
             ..Default::default()
         };
         let runtime = deno_core::JsRuntime::new(rt_options);
 
-        let mut rule_executor = Self { runtime, rule_map };
+        let cache_root = HttpCache::default_root();
+        let mut rule_executor = Self {
+            runtime,
+            rule_map,
+            rule_namespaces: DashMap::new(),
+            last_registered_rule,
+            current_rule,
+            diagnostics,
+            rule_cache: crate::rule_cache::RuleCache::new(cache_root.clone()),
+            rule_sources: DashMap::new(),
+            reload,
+            emit_cache: EmitCache::new(cache_root),
+        };
 
         rule_executor.setup()?;
 
@@ -141,28 +346,102 @@ impl RuleExecutor {
     }
 
     pub async fn load_file(&mut self, file: PathBuf) -> Result<(), RuleExecutorError> {
+        let bytes = fs::read(&file)
+            .await
+            .map_err(|err| RuleExecutorError::CouldNotReadFile {
+                file: file.clone(),
+                err,
+            })?;
+
+        let checksum = crate::rule_cache::RuleCache::checksum(&bytes, JS_SNAPSHOT);
+        if !self.reload {
+            if let Some(rule) = self.rule_cache.get(checksum).await {
+                let rule_id = RuleId::next();
+                self.rule_sources.insert(rule_id.clone(), file.clone());
+                self.rule_map.insert(rule_id, rule);
+                return Ok(());
+            }
+        }
+
+        let rule_id = self.evaluate_rule_file(&file, bytes).await?;
+
+        if let Some(rule_id) = rule_id {
+            if let Some(rule) = self.rule_map.get(&rule_id) {
+                let _ = self.rule_cache.set(checksum, rule.value()).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shims, transpiles (if needed), and evaluates a rule file's bytes,
+    /// recording which file the resulting `RuleId` (if any) came from in
+    /// `rule_sources` so `lint_file` can re-evaluate it later on demand.
+    /// Shared by `load_file` and `lint_file`'s cache-hit fallback so both
+    /// paths transpile local `.ts`/`.tsx`/`.jsx` rule files the same way.
+    async fn evaluate_rule_file(
+        &mut self,
+        file: &PathBuf,
+        bytes: Vec<u8>,
+    ) -> Result<Option<RuleId>, RuleExecutorError> {
         let module_name = format!("file://{}", file.to_str().unwrap());
-        let module_code =
-            fs::read_to_string(&file)
+        let mod_specifier =
+            url::Url::parse(&module_name).map_err(|reason| RuleExecutorError::BadModuleName {
+                module_name: module_name.clone(),
+                reason,
+            })?;
+        let module_code = String::from_utf8(bytes).map_err(|err| RuleExecutorError::CouldNotReadFile {
+            file: file.clone(),
+            err: std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+        })?;
+
+        // `load_side_module` (below, via `load`) evaluates this source
+        // directly rather than going through `NetModuleLoader::load`, so a
+        // local `.ts`/`.tsx`/`.jsx` rule file needs transpiling here too.
+        let media_type = MediaType::from_specifier(&mod_specifier);
+        let module_code = if media_type.requires_transpilation() {
+            transpile(&self.emit_cache, &mod_specifier, media_type, module_code)
                 .await
-                .map_err(|err| RuleExecutorError::CouldNotReadFile {
+                .map_err(|reason| RuleExecutorError::ParseError {
                     file: file.clone(),
-                    err,
-                })?;
-        self.load(&module_name, Some(module_code)).await
+                    media_type,
+                    reason,
+                })?
+        } else {
+            module_code
+        };
+
+        let rule_id = self.load(&module_name, Some(module_code)).await?;
+        if let Some(rule_id) = rule_id.clone() {
+            self.rule_sources.insert(rule_id, file.clone());
+        }
+
+        Ok(rule_id)
     }
 
+    /// Loads and evaluates a module, returning the id of the rule it
+    /// registered via `op_escheck_rule_new`, if any.
     pub async fn load(
         &mut self,
         module_name: &str,
         module_code: Option<String>,
-    ) -> Result<(), RuleExecutorError> {
+    ) -> Result<Option<RuleId>, RuleExecutorError> {
         let mod_specifier =
             url::Url::parse(module_name).map_err(|reason| RuleExecutorError::BadModuleName {
                 module_name: module_name.to_string(),
                 reason,
             })?;
 
+        let module_code = match module_code {
+            Some(code) => Some(Self::shim_commonjs_if_needed(&mod_specifier, code).await.map_err(
+                |reason| RuleExecutorError::CjsShimError {
+                    module_name: module_name.to_string(),
+                    reason,
+                },
+            )?),
+            None => None,
+        };
+
         let mod_id = self
             .runtime
             .load_side_module(&mod_specifier, module_code)
@@ -183,14 +462,154 @@ impl RuleExecutor {
 
         let _ = eval_future.await.unwrap();
 
-        self.runtime
-            .get_module_namespace(mod_id)
-            .map_err(|reason| RuleExecutorError::ModuleEvaluationError {
-                module_name: module_name.to_string(),
-                reason,
+        let namespace =
+            self.runtime
+                .get_module_namespace(mod_id)
+                .map_err(|reason| RuleExecutorError::ModuleEvaluationError {
+                    module_name: module_name.to_string(),
+                    reason,
+                })?;
+
+        let rule_id = self.last_registered_rule.lock().unwrap().take();
+        if let Some(rule_id) = rule_id.clone() {
+            self.rule_namespaces.insert(rule_id, namespace);
+        }
+
+        Ok(rule_id)
+    }
+
+    /// Re-evaluates the file `rule_id` was last loaded from (tracked in
+    /// `rule_sources`) to obtain a fresh module namespace, then stores it
+    /// under the original `rule_id` so future lookups succeed too.
+    /// Evaluation always assigns a brand-new `RuleId` via
+    /// `op_escheck_rule_new`, so the namespace can't simply be looked up
+    /// after the fact — it has to be carried over by hand.
+    async fn reevaluate_rule_namespace(
+        &mut self,
+        rule_id: &RuleId,
+    ) -> Result<v8::Global<v8::Value>, RuleExecutorError> {
+        let file = self
+            .rule_sources
+            .get(rule_id)
+            .ok_or(RuleExecutorError::UnknownRule {
+                rule_id: rule_id.clone(),
+            })?
+            .clone();
+
+        let bytes = fs::read(&file)
+            .await
+            .map_err(|err| RuleExecutorError::CouldNotReadFile {
+                file: file.clone(),
+                err,
             })?;
 
-        Ok(())
+        let new_rule_id = self
+            .evaluate_rule_file(&file, bytes)
+            .await?
+            .ok_or(RuleExecutorError::UnknownRule {
+                rule_id: rule_id.clone(),
+            })?;
+
+        // `evaluate_rule_file` -> `load` -> `op_escheck_rule_new` always mints
+        // a fresh `RuleId` for this re-evaluation, distinct from the one
+        // `lint_file` was actually asked about. Carry the namespace over to
+        // the original id and drop the throwaway one everywhere it landed,
+        // so `rule_map`/`rule_namespaces` don't end up with a duplicate rule.
+        let (_, namespace) = self.rule_namespaces.remove(&new_rule_id).ok_or(
+            RuleExecutorError::UnknownRule {
+                rule_id: rule_id.clone(),
+            },
+        )?;
+        self.rule_map.remove(&new_rule_id);
+        self.rule_sources.remove(&new_rule_id);
+
+        self.rule_namespaces.insert(rule_id.clone(), namespace.clone());
+
+        Ok(namespace)
+    }
+
+    /// Runs a single rule's `create(context)` visitor against `target_file`
+    /// and returns every diagnostic it reported. `rule_id` must name a rule
+    /// previously registered by a `load`/`load_file` call in this same
+    /// `RuleExecutor` (op_escheck_rule_new associates the two).
+    pub async fn lint_file(
+        &mut self,
+        rule_id: RuleId,
+        target_file: PathBuf,
+    ) -> Result<Vec<crate::lint::Diagnostic>, RuleExecutorError> {
+        let namespace = match self.rule_namespaces.get(&rule_id) {
+            Some(namespace) => namespace.clone(),
+            // `rule_id` was served from `rule_cache`, which only stores
+            // metadata: there's no module namespace to run a visitor from
+            // yet, so re-evaluate the rule file it came from and retry.
+            None => self.reevaluate_rule_namespace(&rule_id).await?,
+        };
+
+        let source =
+            fs::read_to_string(&target_file)
+                .await
+                .map_err(|err| RuleExecutorError::CouldNotReadFile {
+                    file: target_file.clone(),
+                    err,
+                })?;
+
+        let specifier = url::Url::from_file_path(&target_file).map_err(|_| {
+            RuleExecutorError::BadModuleName {
+                module_name: target_file.display().to_string(),
+                reason: url::ParseError::RelativeUrlWithoutBase,
+            }
+        })?;
+        let media_type = MediaType::from_specifier(&specifier);
+
+        let parsed = deno_ast::parse_module(deno_ast::ParseParams {
+            specifier: specifier.to_string(),
+            text_info: deno_ast::SourceTextInfo::from_string(source.clone()),
+            media_type: media_type.as_deno_ast_media_type(),
+            capture_tokens: false,
+            scope_analysis: false,
+            maybe_syntax: None,
+        })
+        .map_err(|reason| RuleExecutorError::ParseError {
+            file: target_file.clone(),
+            media_type,
+            reason: reason.into(),
+        })?;
+
+        let visitor = crate::lint::run_create(&mut self.runtime, &namespace, &source)
+            .map_err(RuleExecutorError::DenoExecutionError)?;
+
+        *self.current_rule.lock().unwrap() = Some(rule_id);
+        self.diagnostics.lock().unwrap().clear();
+
+        let module = parsed
+            .program_ref()
+            .as_module()
+            .ok_or_else(|| anyhow::anyhow!("{:?} did not parse as a module", target_file))
+            .map_err(RuleExecutorError::DenoExecutionError)?;
+
+        let walk_result = crate::lint::walk(&mut self.runtime, &visitor, module);
+
+        *self.current_rule.lock().unwrap() = None;
+        walk_result.map_err(RuleExecutorError::DenoExecutionError)?;
+
+        Ok(self.diagnostics.lock().unwrap().drain(..).collect())
+    }
+
+    /// Most published ESLint rules are still CommonJS. If `source` looks
+    /// like one (see [`crate::cjs::is_commonjs`]), rewrite it into the
+    /// `module.exports`-shimmed ES module `load_side_module` expects;
+    /// otherwise pass it through untouched.
+    async fn shim_commonjs_if_needed(
+        specifier: &url::Url,
+        source: String,
+    ) -> Result<String, anyhow::Error> {
+        let package_json_is_esm = package_json_declares_esm(specifier).await;
+
+        if crate::cjs::is_commonjs(specifier, &source, package_json_is_esm) {
+            crate::cjs::to_esm_shim(specifier, source)
+        } else {
+            Ok(source)
+        }
     }
 
     pub fn setup(&mut self) -> Result<(), RuleExecutorError> {